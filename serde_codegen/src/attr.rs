@@ -0,0 +1,571 @@
+use aster;
+
+use syntax::ast::{self, Item, MetaItem, MetaItemKind, Lit_};
+use syntax::ext::base::ExtCtxt;
+use syntax::parse::token::InternedString;
+use syntax::ptr::P;
+
+use error::Error;
+
+/// The name of a container, field or variant as used when serializing and
+/// deserializing, after any `#[serde(rename = "...")]`.
+#[derive(Debug, Clone)]
+pub struct Name {
+    ser_name: InternedString,
+    de_name: InternedString,
+}
+
+impl Name {
+    fn new(name: InternedString) -> Self {
+        Name {
+            ser_name: name.clone(),
+            de_name: name,
+        }
+    }
+
+    /// Return the name used when deserializing.
+    pub fn deserialize_name(&self) -> InternedString {
+        self.de_name.clone()
+    }
+
+    /// Return an expression that yields the name used when deserializing,
+    /// for embedding in generated code.
+    pub fn deserialize_name_expr(&self) -> P<ast::Expr> {
+        let builder = aster::AstBuilder::new();
+        builder.expr().str(&self.de_name[..])
+    }
+}
+
+/// How an enum's variant is recorded in the serialized form, controlled by
+/// the container-level `#[serde(tag = "...")]` / `#[serde(untagged)]`
+/// attributes.
+#[derive(Debug, Clone)]
+pub enum EnumTag {
+    /// The variant is stored alongside its content as the sole key of a
+    /// one-entry map, e.g. `{"Variant": <content>}`. This is the default
+    /// when no `tag`/`untagged` attribute is given.
+    External,
+    /// `#[serde(tag = "t")]`: the variant name is stored under the key `t`
+    /// inside the same map as the variant's own fields.
+    Internal { tag: InternedString },
+    /// `#[serde(tag = "t", content = "c")]`: the variant name is stored
+    /// under `t` and its content under `c` of a two-field map.
+    Adjacent { tag: InternedString, content: InternedString },
+    /// `#[serde(untagged)]`: no tag is written at all; deserialization
+    /// tries each variant in declaration order until one succeeds.
+    None,
+}
+
+/// How a container is deserialized, controlled by the container-level
+/// `#[serde(from = "...")]` / `#[serde(try_from = "...")]` attributes.
+pub enum FromType {
+    /// No proxy type; deserialize the container directly.
+    None,
+    /// `#[serde(from = "Proxy")]`: deserialize a `Proxy`, then convert it
+    /// with `Proxy::into()`.
+    From(P<ast::Ty>),
+    /// `#[serde(try_from = "Proxy")]`: deserialize a `Proxy`, then convert
+    /// it with `TryFrom::try_from`, mapping a conversion failure through
+    /// `Error::custom`.
+    TryFrom(P<ast::Ty>),
+}
+
+/// Represents container (struct or enum) attributes.
+pub struct ContainerAttrs {
+    name: Name,
+    deny_unknown_fields: bool,
+    tag: EnumTag,
+    bound: Option<Vec<ast::WherePredicate>>,
+    default: bool,
+    from_type: FromType,
+}
+
+impl ContainerAttrs {
+    /// Extract the `#[serde(...)]` attributes from an item.
+    pub fn from_item(cx: &ExtCtxt, item: &Item) -> Result<Self, Error> {
+        let mut name = Name::new(item.ident.name.as_str());
+        let mut deny_unknown_fields = false;
+        let mut tag = None;
+        let mut content = None;
+        let mut untagged = false;
+        let mut bound = None;
+        let mut default = false;
+        let mut from = None;
+        let mut try_from = None;
+
+        for meta_items in item.attrs.iter().filter_map(get_serde_meta_items) {
+            for meta_item in meta_items {
+                match meta_item.node {
+                    // Parse `#[serde(rename = "foo")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "rename" => {
+                        if let Ok(s) = get_str_from_lit(cx, "rename", lit) {
+                            name = Name::new(s);
+                        }
+                    }
+
+                    // Parse `#[serde(deny_unknown_fields)]`
+                    MetaItemKind::Word(ref name_) if name_ == "deny_unknown_fields" => {
+                        deny_unknown_fields = true;
+                    }
+
+                    // Parse `#[serde(tag = "t")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "tag" => {
+                        if let Ok(s) = get_str_from_lit(cx, "tag", lit) {
+                            tag = Some(s);
+                        }
+                    }
+
+                    // Parse `#[serde(content = "c")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "content" => {
+                        if let Ok(s) = get_str_from_lit(cx, "content", lit) {
+                            content = Some(s);
+                        }
+                    }
+
+                    // Parse `#[serde(untagged)]`
+                    MetaItemKind::Word(ref name_) if name_ == "untagged" => {
+                        untagged = true;
+                    }
+
+                    // Parse `#[serde(bound = "T: MyTrait")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "bound" => {
+                        if let Ok(s) = get_str_from_lit(cx, "bound", lit) {
+                            bound = Some(try!(parse_where_predicates(cx, &s)));
+                        }
+                    }
+
+                    // Parse `#[serde(default)]`
+                    MetaItemKind::Word(ref name_) if name_ == "default" => {
+                        default = true;
+                    }
+
+                    // Parse `#[serde(from = "Proxy")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "from" => {
+                        if let Ok(s) = get_str_from_lit(cx, "from", lit) {
+                            from = Some(try!(parse_lit_str_as_ty(cx, &s)));
+                        }
+                    }
+
+                    // Parse `#[serde(try_from = "Proxy")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "try_from" => {
+                        if let Ok(s) = get_str_from_lit(cx, "try_from", lit) {
+                            try_from = Some(try!(parse_lit_str_as_ty(cx, &s)));
+                        }
+                    }
+
+                    _ => {
+                        cx.span_err(
+                            meta_item.span,
+                            &format!("unknown serde container attribute `{}`",
+                                     meta_item_to_string(meta_item)));
+                        return Err(Error);
+                    }
+                }
+            }
+        }
+
+        let from_type = match (from, try_from) {
+            (Some(from), None) => FromType::From(from),
+            (None, Some(try_from)) => FromType::TryFrom(try_from),
+            (None, None) => FromType::None,
+            (Some(_), Some(_)) => {
+                cx.span_err(
+                    item.span,
+                    "`#[serde(from = \"...\")]` cannot be combined with `#[serde(try_from = \"...\")]`");
+                return Err(Error);
+            }
+        };
+
+        let tag = if untagged {
+            if tag.is_some() || content.is_some() {
+                cx.span_err(
+                    item.span,
+                    "`#[serde(untagged)]` cannot be combined with `tag`/`content`");
+                return Err(Error);
+            }
+            EnumTag::None
+        } else {
+            match (tag, content) {
+                (Some(tag), Some(content)) => EnumTag::Adjacent { tag: tag, content: content },
+                (Some(tag), None) => EnumTag::Internal { tag: tag },
+                (None, Some(_)) => {
+                    cx.span_err(
+                        item.span,
+                        "`#[serde(content = \"...\")]` requires `tag` to also be set");
+                    return Err(Error);
+                }
+                (None, None) => EnumTag::External,
+            }
+        };
+
+        Ok(ContainerAttrs {
+            name: name,
+            deny_unknown_fields: deny_unknown_fields,
+            tag: tag,
+            bound: bound,
+            default: default,
+            from_type: from_type,
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn deny_unknown_fields(&self) -> bool {
+        self.deny_unknown_fields
+    }
+
+    pub fn tag(&self) -> &EnumTag {
+        &self.tag
+    }
+
+    /// An explicit `#[serde(bound = "...")]` where-clause, if the user gave
+    /// one. When present, this replaces the `Deserialize` bounds that would
+    /// otherwise be inferred from field usage.
+    pub fn bound(&self) -> Option<&[ast::WherePredicate]> {
+        self.bound.as_ref().map(|v| &v[..])
+    }
+
+    /// The `#[serde(from = "...")]` / `#[serde(try_from = "...")]` proxy
+    /// type this container deserializes through, if either was given.
+    pub fn from_type(&self) -> &FromType {
+        &self.from_type
+    }
+
+    /// Whether `#[serde(default)]` was given on the container. When set,
+    /// every field behaves as though it individually had `#[serde(default)]`
+    /// unless it specifies its own default.
+    pub fn default(&self) -> bool {
+        self.default
+    }
+}
+
+/// Represents variant attributes.
+pub struct VariantAttrs {
+    name: Name,
+    aliases: Vec<InternedString>,
+    other: bool,
+}
+
+impl VariantAttrs {
+    pub fn from_variant(cx: &ExtCtxt, variant: &ast::Variant) -> Result<Self, Error> {
+        let mut name = Name::new(variant.node.name.name.as_str());
+        let mut aliases = vec![];
+        let mut other = false;
+
+        for meta_items in variant.node.attrs.iter().filter_map(get_serde_meta_items) {
+            for meta_item in meta_items {
+                match meta_item.node {
+                    // Parse `#[serde(rename = "foo")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "rename" => {
+                        if let Ok(s) = get_str_from_lit(cx, "rename", lit) {
+                            name = Name::new(s);
+                        }
+                    }
+
+                    // Parse `#[serde(alias = "foo")]`, repeatable.
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "alias" => {
+                        if let Ok(s) = get_str_from_lit(cx, "alias", lit) {
+                            aliases.push(s);
+                        }
+                    }
+
+                    // Parse `#[serde(other)]`
+                    MetaItemKind::Word(ref name_) if name_ == "other" => {
+                        other = true;
+                    }
+
+                    _ => {
+                        cx.span_err(
+                            meta_item.span,
+                            &format!("unknown serde variant attribute `{}`",
+                                     meta_item_to_string(meta_item)));
+                        return Err(Error);
+                    }
+                }
+            }
+        }
+
+        Ok(VariantAttrs {
+            name: name,
+            aliases: aliases,
+            other: other,
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// Additional names that also deserialize to this variant, from
+    /// repeated `#[serde(alias = "...")]` attributes.
+    pub fn aliases(&self) -> &[InternedString] {
+        &self.aliases
+    }
+
+    /// Whether `#[serde(other)]` was given on this variant: an unrecognized
+    /// tag is deserialized as this variant instead of failing. Only
+    /// internally and adjacently tagged enums accept it; on an externally
+    /// tagged or untagged enum it is a compile-time error.
+    pub fn other(&self) -> bool {
+        self.other
+    }
+}
+
+/// Represents field attributes.
+pub struct FieldAttrs {
+    name: Name,
+    skip_serializing: bool,
+    skip_deserializing: bool,
+    deserialize_with: Option<P<ast::Expr>>,
+    missing_expr: P<ast::Expr>,
+    bound: Option<Vec<ast::WherePredicate>>,
+    aliases: Vec<InternedString>,
+}
+
+impl FieldAttrs {
+    pub fn from_field(
+        cx: &ExtCtxt,
+        _container_ty: &P<ast::Ty>,
+        _generics: &ast::Generics,
+        field: &ast::StructField,
+        _is_enum: bool,
+        container_default: bool,
+    ) -> Result<Self, Error> {
+        let field_ident = match field.node.kind {
+            ast::NamedField(ident, _) => ident,
+            ast::UnnamedField(index) => {
+                cx.span_bug(field.span, &format!("unexpected unnamed field {}", index))
+            }
+        };
+
+        let mut name = Name::new(field_ident.name.as_str());
+        let mut skip_serializing = false;
+        let mut skip_deserializing = false;
+        let mut deserialize_with = None;
+        let mut bound = None;
+        let mut default = false;
+        let mut default_path = None;
+        let mut aliases = vec![];
+
+        for meta_items in field.node.attrs.iter().filter_map(get_serde_meta_items) {
+            for meta_item in meta_items {
+                match meta_item.node {
+                    // Parse `#[serde(rename = "foo")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "rename" => {
+                        if let Ok(s) = get_str_from_lit(cx, "rename", lit) {
+                            name = Name::new(s);
+                        }
+                    }
+
+                    // Parse `#[serde(skip_serializing)]`
+                    MetaItemKind::Word(ref name_) if name_ == "skip_serializing" => {
+                        skip_serializing = true;
+                    }
+
+                    // Parse `#[serde(skip_deserializing)]`
+                    MetaItemKind::Word(ref name_) if name_ == "skip_deserializing" => {
+                        skip_deserializing = true;
+                    }
+
+                    // Parse `#[serde(deserialize_with = "...")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "deserialize_with" => {
+                        if let Ok(s) = get_str_from_lit(cx, "deserialize_with", lit) {
+                            let path = aster::AstBuilder::new().path().ids(s.split("::")).build();
+                            deserialize_with = Some(
+                                aster::AstBuilder::new().expr().call()
+                                    .build_path(path)
+                                    .arg().id("deserializer")
+                                    .build()
+                            );
+                        }
+                    }
+
+                    // Parse `#[serde(bound = "T: MyTrait")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "bound" => {
+                        if let Ok(s) = get_str_from_lit(cx, "bound", lit) {
+                            bound = Some(try!(parse_where_predicates(cx, &s)));
+                        }
+                    }
+
+                    // Parse `#[serde(default)]`
+                    MetaItemKind::Word(ref name_) if name_ == "default" => {
+                        default = true;
+                    }
+
+                    // Parse `#[serde(default = "path::to::fn")]`
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "default" => {
+                        if let Ok(s) = get_str_from_lit(cx, "default", lit) {
+                            let path = aster::AstBuilder::new().path().ids(s.split("::")).build();
+                            default_path = Some(
+                                aster::AstBuilder::new().expr().call()
+                                    .build_path(path)
+                                    .build()
+                            );
+                        }
+                    }
+
+                    // Parse `#[serde(alias = "foo")]`, repeatable.
+                    MetaItemKind::NameValue(ref name_, ref lit) if name_ == "alias" => {
+                        if let Ok(s) = get_str_from_lit(cx, "alias", lit) {
+                            aliases.push(s);
+                        }
+                    }
+
+                    _ => {
+                        cx.span_err(
+                            meta_item.span,
+                            &format!("unknown serde field attribute `{}`",
+                                     meta_item_to_string(meta_item)));
+                        return Err(Error);
+                    }
+                }
+            }
+        }
+
+        let missing_expr = {
+            let name_expr = name.deserialize_name_expr();
+            match default_path {
+                Some(default_path) => default_path,
+                None if default || container_default => {
+                    quote_expr!(cx, ::std::default::Default::default())
+                }
+                None => {
+                    quote_expr!(cx, return Err(::serde::de::Error::missing_field($name_expr)))
+                }
+            }
+        };
+
+        Ok(FieldAttrs {
+            name: name,
+            skip_serializing: skip_serializing,
+            skip_deserializing: skip_deserializing,
+            deserialize_with: deserialize_with,
+            missing_expr: missing_expr,
+            bound: bound,
+            aliases: aliases,
+        })
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// Additional names that also deserialize to this field, from repeated
+    /// `#[serde(alias = "...")]` attributes.
+    pub fn aliases(&self) -> &[InternedString] {
+        &self.aliases
+    }
+
+    pub fn skip_serializing(&self) -> bool {
+        self.skip_serializing
+    }
+
+    pub fn skip_deserializing(&self) -> bool {
+        self.skip_deserializing
+    }
+
+    pub fn deserialize_with(&self) -> Option<&P<ast::Expr>> {
+        self.deserialize_with.as_ref()
+    }
+
+    /// The expression to evaluate when a field is missing from the input,
+    /// used both by the sequence path and the map path of the generated
+    /// `Deserialize` impl. This is `Default::default()` or a call to the
+    /// named constructor when the field (or its container) carries
+    /// `#[serde(default)]` / `#[serde(default = "...")]`, and an error
+    /// otherwise.
+    pub fn expr_is_missing(&self) -> P<ast::Expr> {
+        self.missing_expr.clone()
+    }
+
+    /// An explicit `#[serde(bound = "...")]` where-clause on this field, if
+    /// the user gave one. When present, this field contributes those
+    /// predicates to the impl's where-clause instead of the bound that
+    /// would otherwise be inferred for the type parameters it mentions.
+    pub fn bound(&self) -> Option<&[ast::WherePredicate]> {
+        self.bound.as_ref().map(|v| &v[..])
+    }
+}
+
+/// Return the `MetaItem`s inside a top-level `#[serde(...)]` attribute, if
+/// this attribute is one.
+fn get_serde_meta_items(attr: &ast::Attribute) -> Option<&[P<MetaItem>]> {
+    match attr.node.value.node {
+        MetaItemKind::List(ref name, ref items) if name == "serde" => Some(items),
+        _ => None,
+    }
+}
+
+fn get_str_from_lit(
+    cx: &ExtCtxt,
+    attr_name: &str,
+    lit: &ast::Lit,
+) -> Result<InternedString, Error> {
+    match lit.node {
+        Lit_::LitStr(ref s, _) => Ok(s.clone()),
+        _ => {
+            cx.span_err(
+                lit.span,
+                &format!("serde attribute `{}` requires a string value", attr_name));
+            Err(Error)
+        }
+    }
+}
+
+/// Parse a `#[serde(bound = "...")]` value as a comma-separated list of
+/// where-clause predicates, by parsing it the same way the compiler would
+/// parse the predicates following a real `where` keyword.
+fn parse_where_predicates(
+    cx: &ExtCtxt,
+    bound_str: &str,
+) -> Result<Vec<ast::WherePredicate>, Error> {
+    let where_string = format!("where {}", bound_str);
+
+    let mut parser = ::syntax::parse::new_parser_from_source_str(
+        cx.parse_sess(),
+        cx.cfg(),
+        "<serde bound attribute>".to_string(),
+        where_string,
+    );
+
+    match parser.parse_where_clause() {
+        Ok(where_clause) => Ok(where_clause.predicates),
+        Err(mut err) => {
+            err.emit();
+            Err(Error)
+        }
+    }
+}
+
+/// Parse a `#[serde(from = "...")]` / `#[serde(try_from = "...")]` value as
+/// a type, the same way the compiler would parse a type appearing anywhere
+/// else in the source.
+fn parse_lit_str_as_ty(
+    cx: &ExtCtxt,
+    ty_str: &str,
+) -> Result<P<ast::Ty>, Error> {
+    let mut parser = ::syntax::parse::new_parser_from_source_str(
+        cx.parse_sess(),
+        cx.cfg(),
+        "<serde from/try_from attribute>".to_string(),
+        ty_str.to_string(),
+    );
+
+    match parser.parse_ty() {
+        Ok(ty) => Ok(ty),
+        Err(mut err) => {
+            err.emit();
+            Err(Error)
+        }
+    }
+}
+
+fn meta_item_to_string(meta_item: &MetaItem) -> String {
+    match meta_item.node {
+        MetaItemKind::Word(ref name) => name.to_string(),
+        MetaItemKind::NameValue(ref name, _) => name.to_string(),
+        MetaItemKind::List(ref name, _) => name.to_string(),
+    }
+}
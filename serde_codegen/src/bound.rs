@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+
+use syntax::ast;
+
+/// Given a type and the list of a generic item's type parameter idents,
+/// return the subset of those idents that occur syntactically somewhere
+/// inside the type.
+///
+/// This lets us add a `Deserialize` bound only to the type parameters a
+/// field actually deserializes through, rather than to every type
+/// parameter the container happens to declare -- the latter falls over when
+/// `T` only appears in fields that are never deserialized, so `T` may not
+/// implement `Deserialize` at all. The walk is purely syntactic: a
+/// `PhantomData<T>` type still reports `T`.
+pub fn ty_params_in_ty(params: &[ast::Ident], ty: &ast::Ty) -> BTreeSet<ast::Ident> {
+    let mut found = BTreeSet::new();
+    walk_ty(params, ty, &mut found);
+    found
+}
+
+fn walk_ty(params: &[ast::Ident], ty: &ast::Ty, found: &mut BTreeSet<ast::Ident>) {
+    match ty.node {
+        ast::TyKind::Path(ref qself, ref path) => {
+            // `<T as Trait>::Assoc` mentions `T` through the `QSelf`, not
+            // through the path segments below.
+            if let Some(ref qself) = *qself {
+                walk_ty(params, &qself.ty, found);
+            }
+
+            if let Some(segment) = path.segments.last() {
+                if path.segments.len() == 1 && params.contains(&segment.identifier) {
+                    found.insert(segment.identifier);
+                }
+            }
+
+            for segment in &path.segments {
+                if let ast::PathParameters::AngleBracketed(ref data) = segment.parameters {
+                    for ty in data.types.iter() {
+                        walk_ty(params, ty, found);
+                    }
+                }
+            }
+        }
+
+        ast::TyKind::Rptr(_, ref mut_ty) => walk_ty(params, &mut_ty.ty, found),
+        ast::TyKind::Ptr(ref mut_ty) => walk_ty(params, &mut_ty.ty, found),
+        ast::TyKind::Paren(ref ty) => walk_ty(params, ty, found),
+        ast::TyKind::Slice(ref ty) => walk_ty(params, ty, found),
+        ast::TyKind::FixedLengthVec(ref ty, _) => walk_ty(params, ty, found),
+
+        ast::TyKind::Tup(ref tys) => {
+            for ty in tys {
+                walk_ty(params, ty, found);
+            }
+        }
+
+        // Bare fn pointers, trait objects (`ObjectSum`), and anything else
+        // this walk doesn't specifically understand might still reference
+        // a type parameter in a position we can't see into. Conservatively
+        // treat every parameter in scope as used rather than risk dropping
+        // a bound the generated impl actually needs -- a stricter-than-
+        // necessary bound still compiles, a missing one doesn't.
+        _ => {
+            found.extend(params.iter().cloned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use aster;
+    use syntax::ast;
+
+    use super::ty_params_in_ty;
+
+    fn ident(name: &str) -> ast::Ident {
+        aster::AstBuilder::new().id(name)
+    }
+
+    fn params(names: &[&str]) -> Vec<ast::Ident> {
+        names.iter().map(|name| ident(name)).collect()
+    }
+
+    #[test]
+    fn finds_bare_type_param() {
+        let builder = aster::AstBuilder::new();
+        let ty = builder.ty().id("T");
+
+        let found = ty_params_in_ty(&params(&["T"]), &ty);
+
+        assert_eq!(found, vec![ident("T")].into_iter().collect::<BTreeSet<_>>());
+    }
+
+    #[test]
+    fn ignores_unrelated_type_param() {
+        let builder = aster::AstBuilder::new();
+        let ty = builder.ty().id("U");
+
+        let found = ty_params_in_ty(&params(&["T"]), &ty);
+
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn finds_type_param_nested_in_generic_container() {
+        // `Vec<T>` should surface `T` even though `T` isn't the field's own
+        // top-level type.
+        let builder = aster::AstBuilder::new();
+        let ty = builder.ty().path()
+            .segment("Vec").with_ty(builder.ty().id("T")).build()
+            .build();
+
+        let found = ty_params_in_ty(&params(&["T"]), &ty);
+
+        assert_eq!(found, vec![ident("T")].into_iter().collect::<BTreeSet<_>>());
+    }
+
+    #[test]
+    fn finds_type_param_behind_phantom_data() {
+        // `PhantomData<T>` never actually deserializes a `T`, but the walk
+        // is purely syntactic and doesn't know that -- it should still
+        // report `T` as present, not silently drop it.
+        let builder = aster::AstBuilder::new();
+        let ty = builder.ty().phantom_data().id("T");
+
+        let found = ty_params_in_ty(&params(&["T"]), &ty);
+
+        assert_eq!(found, vec![ident("T")].into_iter().collect::<BTreeSet<_>>());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use aster;
 
 use syntax::ast::{
@@ -14,6 +16,8 @@ use syntax::parse::token::InternedString;
 use syntax::ptr::P;
 
 use attr;
+use attr::EnumTag;
+use bound;
 use error::Error;
 
 pub fn expand_derive_deserialize(
@@ -46,17 +50,21 @@ pub fn expand_derive_deserialize(
         }
     };
 
-    let impl_generics = builder.from_generics(generics.clone())
-        .add_ty_param_bound(
-            builder.path().global().ids(&["serde", "de", "Deserialize"]).build()
-        )
-        .build();
+    let container_attrs = match attr::ContainerAttrs::from_item(cx, item) {
+        Ok(container_attrs) => container_attrs,
+        Err(Error) => return,
+    };
+
+    let impl_generics = match build_impl_generics(cx, &builder, item, generics, &container_attrs) {
+        Ok(impl_generics) => impl_generics,
+        Err(Error) => return,
+    };
 
     let ty = builder.ty().path()
         .segment(item.ident).with_generics(impl_generics.clone()).build()
         .build();
 
-    let body = match deserialize_body(cx, &builder, &item, &impl_generics, ty.clone()) {
+    let body = match deserialize_body(cx, &builder, &item, &impl_generics, ty.clone(), &container_attrs) {
         Ok(body) => body,
         Err(Error) => {
             // An error occured, but it should have been reported already.
@@ -79,14 +87,190 @@ pub fn expand_derive_deserialize(
     push(Annotatable::Item(impl_item))
 }
 
+// Build the `impl<...>` generics for the `Deserialize` impl. By default a
+// type parameter only gets a `Deserialize` bound if it's actually
+// referenced by a field that is deserialized (a `#[serde(skip_deserializing)]`
+// field, such as a `PhantomData<T>` marker, doesn't force a bound on `T`), or, for a
+// `#[serde(from = "...")]`/`try_from` container, by the proxy type instead
+// of the container's own fields. A container- or field-level
+// `#[serde(bound = "...")]` overrides this inference with an explicit
+// where-clause.
+fn build_impl_generics(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    item: &Item,
+    generics: &ast::Generics,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<ast::Generics, Error> {
+    let de_bound = builder.path().global().ids(&["serde", "de", "Deserialize"]).build();
+
+    let generics_with_bound = builder.from_generics(generics.clone())
+        .add_ty_param_bound(de_bound)
+        .build();
+
+    let impl_generics = match container_attrs.bound() {
+        Some(predicates) => {
+            // The container spelled out its own where-clause; don't add a
+            // `Deserialize` bound to any type parameter automatically.
+            let mut impl_generics = builder.from_generics(generics.clone()).build();
+            impl_generics.where_clause.predicates = predicates.to_vec();
+            impl_generics
+        }
+        None => {
+            let (used_ty_params, field_bounds) = match *container_attrs.from_type() {
+                // `#[serde(from = "Proxy")]` / `#[serde(try_from = "Proxy")]`
+                // deserialize the proxy type and convert -- the container's
+                // own fields are never touched, so walking them for type
+                // parameter usage would both miss what the proxy actually
+                // needs and add spurious bounds for parameters the proxy
+                // doesn't mention (e.g. a skipped `PhantomData<T>` sibling).
+                // Infer instead from the proxy type itself.
+                attr::FromType::From(ref proxy_ty) | attr::FromType::TryFrom(ref proxy_ty) => {
+                    let param_idents: Vec<Ident> =
+                        generics.ty_params.iter().map(|ty_param| ty_param.ident).collect();
+                    (bound::ty_params_in_ty(&param_idents, proxy_ty), vec![])
+                }
+                attr::FromType::None => {
+                    try!(deserialize_ty_param_usage(cx, builder, item, generics, container_attrs))
+                }
+            };
+
+            let generics_without_bound = builder.from_generics(generics.clone()).build();
+
+            let ty_params = generics_with_bound.ty_params.iter().cloned()
+                .zip(generics_without_bound.ty_params.iter().cloned())
+                .map(|(with_bound, without_bound)| {
+                    if used_ty_params.contains(&with_bound.ident) {
+                        with_bound
+                    } else {
+                        without_bound
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let mut impl_generics = generics_with_bound;
+            impl_generics.ty_params = P::from_vec(ty_params);
+
+            let mut predicates = impl_generics.where_clause.predicates.to_vec();
+            predicates.extend(field_bounds);
+            impl_generics.where_clause.predicates = predicates;
+
+            impl_generics
+        }
+    };
+
+    Ok(impl_generics)
+}
+
+// Walk every non-skipped field of a struct or enum, collecting the set of
+// type parameters that are actually deserialized through some field, along
+// with any `#[serde(bound = "...")]` predicates declared on individual
+// fields.
+fn deserialize_ty_param_usage(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    item: &Item,
+    generics: &ast::Generics,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<(BTreeSet<Ident>, Vec<ast::WherePredicate>), Error> {
+    let param_idents: Vec<Ident> = generics.ty_params.iter().map(|ty_param| ty_param.ident).collect();
+
+    let is_enum = match item.node {
+        ast::ItemKind::Enum(..) => true,
+        _ => false,
+    };
+
+    // The container type isn't known yet at this point (we're still
+    // assembling the generics it depends on), but `FieldAttrs::from_field`
+    // doesn't use it, so a placeholder is enough.
+    let placeholder_ty = builder.ty().id(item.ident);
+
+    let mut used = BTreeSet::new();
+    let mut field_bounds = vec![];
+
+    for field in struct_fields(item) {
+        // `FieldAttrs::from_field` only understands named fields -- tuple
+        // struct and tuple variant fields don't carry `#[serde(...)]`
+        // attributes anywhere else in this codegen either, so there's
+        // nothing to look up beyond the field's own type.
+        match field.node.kind {
+            ast::UnnamedField(_) => {
+                used.extend(bound::ty_params_in_ty(&param_idents, &field.node.ty));
+                continue;
+            }
+            ast::NamedField(..) => {}
+        }
+
+        let field_attrs = try!(attr::FieldAttrs::from_field(
+            cx,
+            &placeholder_ty,
+            generics,
+            field,
+            is_enum,
+            container_attrs.default(),
+        ));
+
+        if field_attrs.skip_deserializing() {
+            continue;
+        }
+
+        match field_attrs.bound() {
+            Some(predicates) => field_bounds.extend(predicates.iter().cloned()),
+            None => {
+                used.extend(bound::ty_params_in_ty(&param_idents, &field.node.ty));
+            }
+        }
+    }
+
+    Ok((used, field_bounds))
+}
+
+// All the `ast::StructField`s belonging to a struct or enum, across every
+// variant in the enum case.
+fn struct_fields(item: &Item) -> Vec<&ast::StructField> {
+    fn variant_data_fields(variant_data: &ast::VariantData) -> Vec<&ast::StructField> {
+        match *variant_data {
+            ast::VariantData::Struct(ref fields, _) => fields.iter().collect(),
+            ast::VariantData::Tuple(ref fields, _) => fields.iter().collect(),
+            ast::VariantData::Unit(_) => vec![],
+        }
+    }
+
+    match item.node {
+        ast::ItemKind::Struct(ref variant_data, _) => variant_data_fields(variant_data),
+        ast::ItemKind::Enum(ref enum_def, _) => {
+            enum_def.variants.iter()
+                .flat_map(|variant| variant_data_fields(&variant.node.data))
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
 fn deserialize_body(
     cx: &ExtCtxt,
     builder: &aster::AstBuilder,
     item: &Item,
     impl_generics: &ast::Generics,
     ty: P<ast::Ty>,
+    container_attrs: &attr::ContainerAttrs,
 ) -> Result<P<ast::Expr>, Error> {
-    let container_attrs = try!(attr::ContainerAttrs::from_item(cx, item));
+    match *container_attrs.from_type() {
+        attr::FromType::None => {}
+        attr::FromType::From(ref proxy_ty) => {
+            return Ok(quote_expr!(cx, {
+                let __proxy: $proxy_ty = try!(::serde::de::Deserialize::deserialize(deserializer));
+                Ok(::std::convert::Into::into(__proxy))
+            }));
+        }
+        attr::FromType::TryFrom(ref proxy_ty) => {
+            return Ok(quote_expr!(cx, {
+                let __proxy: $proxy_ty = try!(::serde::de::Deserialize::deserialize(deserializer));
+                ::std::convert::TryFrom::try_from(__proxy)
+                    .map_err(::serde::de::Error::custom)
+            }));
+        }
+    }
 
     match item.node {
         ast::ItemKind::Struct(ref variant_data, _) => {
@@ -430,17 +614,33 @@ fn deserialize_struct_as_seq(
     cx: &ExtCtxt,
     builder: &aster::AstBuilder,
     struct_path: ast::Path,
+    container_ty: &P<ast::Ty>,
+    generics: &ast::Generics,
     fields: &[ast::StructField],
+    container_attrs: &attr::ContainerAttrs,
+    is_enum: bool,
 ) -> Result<P<ast::Expr>, Error> {
+    let field_attrs: Vec<_> = try!(
+        fields.iter()
+            .map(|field| attr::FieldAttrs::from_field(
+                cx,
+                container_ty,
+                generics,
+                field,
+                is_enum,
+                container_attrs.default(),
+            ))
+            .collect()
+    );
+
     let let_values: Vec<_> = (0 .. fields.len())
         .map(|i| {
             let name = builder.id(format!("__field{}", i));
+            let missing_expr = field_attrs[i].expr_is_missing();
             quote_stmt!(cx,
                 let $name = match try!(visitor.visit()) {
                     Some(value) => { value },
-                    None => {
-                        return Err(::serde::de::Error::end_of_stream());
-                    }
+                    None => $missing_expr
                 };
             ).unwrap()
         })
@@ -497,7 +697,11 @@ fn deserialize_struct(
         cx,
         builder,
         type_path.clone(),
+        &ty,
+        impl_generics,
         fields,
+        container_attrs,
+        false,
     ));
 
     let (field_visitor, fields_stmt, visit_map_expr) = try!(deserialize_struct_visitor(
@@ -550,24 +754,136 @@ fn deserialize_item_enum(
     ty: P<ast::Ty>,
     enum_def: &EnumDef,
     container_attrs: &attr::ContainerAttrs
+) -> Result<P<ast::Expr>, Error> {
+    match *container_attrs.tag() {
+        EnumTag::External => {
+            deserialize_item_enum_externally_tagged(
+                cx,
+                builder,
+                type_ident,
+                impl_generics,
+                ty,
+                enum_def,
+                container_attrs,
+            )
+        }
+        EnumTag::Internal { ref tag } => {
+            deserialize_item_enum_internally_tagged(
+                cx,
+                builder,
+                type_ident,
+                impl_generics,
+                ty,
+                enum_def,
+                container_attrs,
+                tag.clone(),
+            )
+        }
+        EnumTag::Adjacent { ref tag, ref content } => {
+            deserialize_item_enum_adjacently_tagged(
+                cx,
+                builder,
+                type_ident,
+                impl_generics,
+                ty,
+                enum_def,
+                container_attrs,
+                tag.clone(),
+                content.clone(),
+            )
+        }
+        EnumTag::None => {
+            deserialize_item_enum_untagged(
+                cx,
+                builder,
+                type_ident,
+                impl_generics,
+                ty,
+                enum_def,
+                container_attrs,
+            )
+        }
+    }
+}
+
+// Find the index of the single variant marked `#[serde(other)]`, if any.
+// Only a unit variant may carry this attribute, and only one variant in the
+// enum may have it.
+fn unique_other_variant(
+    cx: &ExtCtxt,
+    enum_def: &EnumDef,
+    variant_attrs: &[attr::VariantAttrs],
+) -> Result<Option<usize>, Error> {
+    let mut other_idx = None;
+
+    for (i, (variant, attrs)) in enum_def.variants.iter().zip(variant_attrs.iter()).enumerate() {
+        if !attrs.other() {
+            continue;
+        }
+
+        match variant.node.data {
+            ast::VariantData::Unit(_) => {}
+            _ => {
+                cx.span_err(variant.span, "`#[serde(other)]` may only be used on a unit variant");
+                return Err(Error);
+            }
+        }
+
+        if other_idx.is_some() {
+            cx.span_err(variant.span, "`#[serde(other)]` may only be used on one variant");
+            return Err(Error);
+        }
+
+        other_idx = Some(i);
+    }
+
+    Ok(other_idx)
+}
+
+fn deserialize_item_enum_externally_tagged(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    impl_generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    enum_def: &EnumDef,
+    container_attrs: &attr::ContainerAttrs
 ) -> Result<P<ast::Expr>, Error> {
     let where_clause = &impl_generics.where_clause;
 
     let type_name = container_attrs.name().deserialize_name_expr();
 
+    let variant_attrs: Vec<_> = try!(
+        enum_def.variants.iter()
+            .map(|variant| attr::VariantAttrs::from_variant(cx, variant))
+            .collect()
+    );
+
+    let other_idx = try!(unique_other_variant(cx, enum_def, &variant_attrs));
+
+    // Unlike internally/adjacently tagged enums, an externally tagged
+    // variant's content is the variant's *entire* body -- there's no
+    // "leftover" content a catch-all could stash and hand to the other
+    // variant's own `Deserialize` logic. Wiring `#[serde(other)]` in here
+    // anyway would make it look unit variants until the tag carries actual
+    // content (`{"Nope": 123}`), at which point it fails at runtime instead
+    // of catching anything. Reject it up front instead, same as untagged.
+    if let Some(idx) = other_idx {
+        cx.span_err(
+            enum_def.variants[idx].span,
+            "`#[serde(other)]` may not be used on an externally tagged enum's variant");
+        return Err(Error);
+    }
+
     let variant_visitor = deserialize_field_visitor(
         cx,
         builder,
-        try!(
-            enum_def.variants.iter()
-                .map(|variant| {
-                    let attrs = try!(attr::VariantAttrs::from_variant(cx, variant));
-                    Ok(attrs.name().deserialize_name())
-                })
-                .collect()
-        ),
+        variant_attrs.iter()
+            .map(|attrs| (attrs.name().deserialize_name(), attrs.aliases().to_vec()))
+            .collect(),
         container_attrs,
         true,
+        other_idx,
     );
 
     let variants_expr = builder.expr().ref_().slice()
@@ -641,102 +957,990 @@ fn deserialize_item_enum(
     }))
 }
 
-fn deserialize_variant(
-    cx: &ExtCtxt,
-    builder: &aster::AstBuilder,
-    type_ident: Ident,
-    generics: &ast::Generics,
-    ty: P<ast::Ty>,
-    variant: &ast::Variant,
-    container_attrs: &attr::ContainerAttrs,
-) -> Result<P<ast::Expr>, Error> {
-    let variant_ident = variant.node.name;
+// Emits a self-contained `Content` buffer type plus a `ContentDeserializer`
+// that can replay a buffered `Content` back through any `Visitor`. This is
+// how internally-tagged, adjacently-tagged, and untagged enums can inspect
+// a map's keys (to find the tag, or to try one variant after another)
+// before committing to a particular variant's real `Deserialize` impl,
+// without requiring every deserializer in existence to support that kind
+// of lookahead.
+fn content_items(cx: &ExtCtxt) -> Vec<P<ast::Item>> {
+    vec![
+        quote_item!(cx,
+            #[derive(Clone)]
+            enum Content {
+                Bool(bool),
+                U64(u64),
+                I64(i64),
+                F64(f64),
+                String(String),
+                Bytes(Vec<u8>),
+                Unit,
+                None,
+                Some(Box<Content>),
+                Seq(Vec<Content>),
+                Map(Vec<(Content, Content)>),
+            }
+        ).unwrap(),
 
-    match variant.node.data {
-        ast::VariantData::Unit(_) => {
-            Ok(quote_expr!(cx, {
-                try!(visitor.visit_unit());
-                Ok($type_ident::$variant_ident)
-            }))
-        }
-        ast::VariantData::Tuple(ref args, _) if args.len() == 1 => {
-            Ok(quote_expr!(cx, {
-                let val = try!(visitor.visit_newtype());
-                Ok($type_ident::$variant_ident(val))
-            }))
-        }
-        ast::VariantData::Tuple(ref fields, _) => {
-            deserialize_tuple_variant(
-                cx,
-                builder,
-                type_ident,
-                variant_ident,
-                generics,
-                ty,
-                fields.len(),
-            )
-        }
-        ast::VariantData::Struct(ref fields, _) => {
-            deserialize_struct_variant(
-                cx,
-                builder,
-                type_ident,
-                variant_ident,
-                generics,
-                ty,
-                fields,
-                container_attrs,
-            )
-        }
-    }
-}
+        quote_item!(cx,
+            struct ContentVisitor;
+        ).unwrap(),
 
-fn deserialize_tuple_variant(
-    cx: &ExtCtxt,
-    builder: &aster::AstBuilder,
-    type_ident: ast::Ident,
-    variant_ident: ast::Ident,
-    generics: &ast::Generics,
-    ty: P<ast::Ty>,
-    fields: usize,
-) -> Result<P<ast::Expr>, Error> {
-    let where_clause = &generics.where_clause;
+        quote_item!(cx,
+            impl ::serde::de::Visitor for ContentVisitor {
+                type Value = Content;
 
-    let (visitor_item, visitor_ty, visitor_expr, visitor_generics) = try!(deserialize_visitor(
-        builder,
-        generics,
-        vec![deserializer_ty_param(builder)],
-        vec![deserializer_ty_arg(builder)],
-    ));
+                #[inline]
+                fn visit_bool<E>(&mut self, value: bool) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::Bool(value))
+                }
 
-    let visit_seq_expr = deserialize_seq(
-        cx,
-        builder,
-        builder.path().id(type_ident).id(variant_ident).build(),
-        fields,
-    );
+                #[inline]
+                fn visit_u64<E>(&mut self, value: u64) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::U64(value))
+                }
 
-    Ok(quote_expr!(cx, {
-        $visitor_item
+                #[inline]
+                fn visit_i64<E>(&mut self, value: i64) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::I64(value))
+                }
 
-        impl $visitor_generics ::serde::de::Visitor for $visitor_ty $where_clause {
-            type Value = $ty;
+                #[inline]
+                fn visit_f64<E>(&mut self, value: f64) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::F64(value))
+                }
 
-            fn visit_seq<__V>(&mut self, mut visitor: __V) -> ::std::result::Result<$ty, __V::Error>
-                where __V: ::serde::de::SeqVisitor,
-            {
-                $visit_seq_expr
-            }
-        }
+                #[inline]
+                fn visit_str<E>(&mut self, value: &str) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::String(value.to_owned()))
+                }
 
-        visitor.visit_tuple($fields, $visitor_expr)
-    }))
-}
+                #[inline]
+                fn visit_string<E>(&mut self, value: String) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::String(value))
+                }
 
-fn deserialize_struct_variant(
-    cx: &ExtCtxt,
-    builder: &aster::AstBuilder,
-    type_ident: ast::Ident,
+                #[inline]
+                fn visit_bytes<E>(&mut self, value: &[u8]) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::Bytes(value.to_vec()))
+                }
+
+                #[inline]
+                fn visit_byte_buf<E>(&mut self, value: Vec<u8>) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::Bytes(value))
+                }
+
+                #[inline]
+                fn visit_unit<E>(&mut self) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::Unit)
+                }
+
+                #[inline]
+                fn visit_none<E>(&mut self) -> ::std::result::Result<Content, E>
+                    where E: ::serde::de::Error,
+                {
+                    Ok(Content::None)
+                }
+
+                #[inline]
+                fn visit_some<D>(&mut self, deserializer: &mut D) -> ::std::result::Result<Content, D::Error>
+                    where D: ::serde::de::Deserializer,
+                {
+                    let value = try!(::serde::de::Deserialize::deserialize(deserializer));
+                    Ok(Content::Some(Box::new(value)))
+                }
+
+                #[inline]
+                fn visit_seq<V>(&mut self, mut visitor: V) -> ::std::result::Result<Content, V::Error>
+                    where V: ::serde::de::SeqVisitor,
+                {
+                    let mut values = Vec::new();
+                    while let Some(value) = try!(visitor.visit()) {
+                        values.push(value);
+                    }
+                    try!(visitor.end());
+                    Ok(Content::Seq(values))
+                }
+
+                #[inline]
+                fn visit_map<V>(&mut self, mut visitor: V) -> ::std::result::Result<Content, V::Error>
+                    where V: ::serde::de::MapVisitor,
+                {
+                    let mut values = Vec::new();
+                    while let Some(key) = try!(visitor.visit_key()) {
+                        let value = try!(visitor.visit_value());
+                        values.push((key, value));
+                    }
+                    try!(visitor.end());
+                    Ok(Content::Map(values))
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl ::serde::de::Deserialize for Content {
+                #[inline]
+                fn deserialize<D>(deserializer: &mut D) -> ::std::result::Result<Content, D::Error>
+                    where D: ::serde::de::Deserializer,
+                {
+                    deserializer.deserialize(ContentVisitor)
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            struct ContentDeserializer<E> {
+                content: Content,
+                err: ::std::marker::PhantomData<E>,
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl<E> ContentDeserializer<E> {
+                fn new(content: Content) -> Self {
+                    ContentDeserializer {
+                        content: content,
+                        err: ::std::marker::PhantomData,
+                    }
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl<E> ::serde::de::Deserializer for ContentDeserializer<E>
+                where E: ::serde::de::Error,
+            {
+                type Error = E;
+
+                #[inline]
+                fn deserialize<V>(&mut self, mut visitor: V) -> ::std::result::Result<V::Value, E>
+                    where V: ::serde::de::Visitor,
+                {
+                    match ::std::mem::replace(&mut self.content, Content::Unit) {
+                        Content::Bool(v) => visitor.visit_bool(v),
+                        Content::U64(v) => visitor.visit_u64(v),
+                        Content::I64(v) => visitor.visit_i64(v),
+                        Content::F64(v) => visitor.visit_f64(v),
+                        Content::String(v) => visitor.visit_string(v),
+                        Content::Bytes(v) => visitor.visit_byte_buf(v),
+                        Content::Unit => visitor.visit_unit(),
+                        Content::None => visitor.visit_none(),
+                        Content::Some(v) => visitor.visit_some(&mut ContentDeserializer::new(*v)),
+                        Content::Seq(v) => visitor.visit_seq(SeqDeserializer::new(v)),
+                        Content::Map(v) => visitor.visit_map(MapDeserializer::new(v)),
+                    }
+                }
+
+                #[inline]
+                fn deserialize_option<V>(&mut self, mut visitor: V) -> ::std::result::Result<V::Value, E>
+                    where V: ::serde::de::Visitor,
+                {
+                    match self.content {
+                        Content::None => visitor.visit_none(),
+                        _ => visitor.visit_some(self),
+                    }
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            struct SeqDeserializer<E> {
+                iter: ::std::vec::IntoIter<Content>,
+                err: ::std::marker::PhantomData<E>,
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl<E> SeqDeserializer<E> {
+                fn new(values: Vec<Content>) -> Self {
+                    SeqDeserializer {
+                        iter: values.into_iter(),
+                        err: ::std::marker::PhantomData,
+                    }
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl<E> ::serde::de::SeqVisitor for SeqDeserializer<E>
+                where E: ::serde::de::Error,
+            {
+                type Error = E;
+
+                #[inline]
+                fn visit<T>(&mut self) -> ::std::result::Result<Option<T>, E>
+                    where T: ::serde::de::Deserialize,
+                {
+                    match self.iter.next() {
+                        Some(value) => {
+                            let mut de = ContentDeserializer::new(value);
+                            ::serde::de::Deserialize::deserialize(&mut de).map(Some)
+                        }
+                        None => Ok(None),
+                    }
+                }
+
+                #[inline]
+                fn end(&mut self) -> ::std::result::Result<(), E> {
+                    Ok(())
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            struct MapDeserializer<E> {
+                iter: ::std::vec::IntoIter<(Content, Content)>,
+                value: Option<Content>,
+                err: ::std::marker::PhantomData<E>,
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl<E> MapDeserializer<E> {
+                fn new(values: Vec<(Content, Content)>) -> Self {
+                    MapDeserializer {
+                        iter: values.into_iter(),
+                        value: None,
+                        err: ::std::marker::PhantomData,
+                    }
+                }
+            }
+        ).unwrap(),
+
+        quote_item!(cx,
+            impl<E> ::serde::de::MapVisitor for MapDeserializer<E>
+                where E: ::serde::de::Error,
+            {
+                type Error = E;
+
+                #[inline]
+                fn visit_key<K>(&mut self) -> ::std::result::Result<Option<K>, E>
+                    where K: ::serde::de::Deserialize,
+                {
+                    match self.iter.next() {
+                        Some((key, value)) => {
+                            self.value = Some(value);
+                            let mut de = ContentDeserializer::new(key);
+                            ::serde::de::Deserialize::deserialize(&mut de).map(Some)
+                        }
+                        None => Ok(None),
+                    }
+                }
+
+                #[inline]
+                fn visit_value<V>(&mut self) -> ::std::result::Result<V, E>
+                    where V: ::serde::de::Deserialize,
+                {
+                    let value = self.value.take().expect("visit_value called before visit_key");
+                    let mut de = ContentDeserializer::new(value);
+                    ::serde::de::Deserialize::deserialize(&mut de)
+                }
+
+                #[inline]
+                fn end(&mut self) -> ::std::result::Result<(), E> {
+                    Ok(())
+                }
+            }
+        ).unwrap(),
+    ]
+}
+
+// Finds the tag's value among a map's entries, removing and returning it
+// along with the remaining entries. Used by internally-tagged enums, where
+// the tag lives alongside the variant's own fields in the same map.
+fn take_tag_from_content(
+    cx: &ExtCtxt,
+    map_ident: P<ast::Expr>,
+    tag_name: &str,
+) -> P<ast::Expr> {
+    let tag_name_expr = aster::AstBuilder::new().expr().str(tag_name);
+
+    quote_expr!(cx, {
+        let mut __map = $map_ident;
+        let __tag_index = __map.iter().position(|&(ref k, _)| {
+            match *k {
+                Content::String(ref s) => &s[..] == $tag_name_expr,
+                _ => false,
+            }
+        });
+
+        match __tag_index {
+            Some(__tag_index) => {
+                let (_, __tag_content) = __map.remove(__tag_index);
+                let __tag = match __tag_content {
+                    Content::String(s) => s,
+                    _ => {
+                        return Err(::serde::de::Error::custom("expected string tag"));
+                    }
+                };
+                (__tag, __map)
+            }
+            None => {
+                return Err(::serde::de::Error::missing_field($tag_name_expr));
+            }
+        }
+    })
+}
+
+// Build a boolean guard expression that matches the tag string against a
+// variant's primary name or any of its `#[serde(alias = "...")]` names, for
+// use in the `match __tag { ref __tag if ... => ... }` arms generated for
+// internally and adjacently tagged enums.
+fn variant_tag_guard_expr(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    variant_attrs: &attr::VariantAttrs,
+) -> P<ast::Expr> {
+    let mut names = vec![variant_attrs.name().deserialize_name()];
+    names.extend(variant_attrs.aliases().iter().cloned());
+    let mut names = names.into_iter();
+
+    let first_expr = builder.expr().str(&names.next().unwrap()[..]);
+    let mut guard_expr = quote_expr!(cx, &__tag[..] == $first_expr);
+
+    for name in names {
+        let name_expr = builder.expr().str(&name[..]);
+        guard_expr = quote_expr!(cx, $guard_expr || &__tag[..] == $name_expr);
+    }
+
+    guard_expr
+}
+
+fn deserialize_item_enum_internally_tagged(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    impl_generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    enum_def: &EnumDef,
+    container_attrs: &attr::ContainerAttrs,
+    tag_name: InternedString,
+) -> Result<P<ast::Expr>, Error> {
+    let content_items = content_items(cx);
+
+    let take_tag_expr = take_tag_from_content(cx, quote_expr!(cx, __map), &tag_name);
+
+    let variant_attrs: Vec<_> = try!(
+        enum_def.variants.iter()
+            .map(|variant| attr::VariantAttrs::from_variant(cx, variant))
+            .collect()
+    );
+
+    let other_idx = try!(unique_other_variant(cx, enum_def, &variant_attrs));
+
+    let mut variant_arms = vec![];
+    for (i, variant) in enum_def.variants.iter().enumerate() {
+        if Some(i) == other_idx {
+            continue;
+        }
+
+        let guard_expr = variant_tag_guard_expr(cx, builder, &variant_attrs[i]);
+
+        let expr = try!(deserialize_internally_tagged_variant(
+            cx,
+            builder,
+            type_ident,
+            impl_generics,
+            ty.clone(),
+            variant,
+            container_attrs,
+        ));
+
+        variant_arms.push(quote_arm!(cx, ref __tag if $guard_expr => { $expr }));
+    }
+
+    let unknown_variant_arm = match other_idx {
+        Some(idx) => {
+            let variant_ident = enum_def.variants[idx].node.name;
+            quote_arm!(cx, _ => { Ok($type_ident::$variant_ident) })
+        }
+        None => quote_arm!(cx, ref __tag => Err(::serde::de::Error::unknown_variant(__tag))),
+    };
+
+    Ok(quote_expr!(cx, {
+        $content_items
+
+        let __content = try!(<Content as ::serde::de::Deserialize>::deserialize(deserializer));
+
+        let (__tag, __rest) = match __content {
+            Content::Map(__map) => $take_tag_expr,
+            _ => {
+                return Err(::serde::de::Error::custom("expected internally tagged enum"));
+            }
+        };
+
+        let mut __deserializer = ContentDeserializer::<__D::Error>::new(Content::Map(__rest));
+
+        match __tag {
+            $variant_arms
+            $unknown_variant_arm
+        }
+    }))
+}
+
+// Builds the pieces needed to deserialize a struct variant's fields out of
+// a `Content::Map` via a `ContentDeserializer`, for use by internally
+// tagged, adjacently tagged, and untagged enums. Unlike the externally
+// tagged path (see `deserialize_struct_variant`), there is no
+// `VariantVisitor` driving things here -- the caller already has a
+// `ContentDeserializer` in hand and just needs a `Visitor` to feed it.
+fn deserialize_struct_variant_from_content(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    variant_ident: Ident,
+    generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    fields: &[ast::StructField],
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<(Vec<P<ast::Item>>, ast::Stmt, P<ast::Expr>), Error> {
+    let type_path = builder.path().id(type_ident).id(variant_ident).build();
+
+    let (field_visitor, fields_stmt, visit_map_expr) = try!(deserialize_struct_visitor(
+        cx,
+        builder,
+        type_path,
+        &ty,
+        generics,
+        fields,
+        container_attrs,
+        true,
+    ));
+
+    let (visitor_item, visitor_ty, visitor_expr, visitor_generics) = try!(deserialize_visitor(
+        builder,
+        generics,
+        vec![],
+        vec![],
+    ));
+
+    let where_clause = &generics.where_clause;
+
+    let mut items = field_visitor;
+    items.push(visitor_item);
+    items.push(quote_item!(cx,
+        impl $visitor_generics ::serde::de::Visitor for $visitor_ty $where_clause {
+            type Value = $ty;
+
+            #[inline]
+            fn visit_map<__V>(&mut self, mut visitor: __V) -> ::std::result::Result<$ty, __V::Error>
+                where __V: ::serde::de::MapVisitor,
+            {
+                $visit_map_expr
+            }
+        }
+    ).unwrap());
+
+    Ok((items, fields_stmt, visitor_expr))
+}
+
+fn deserialize_internally_tagged_variant(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    variant: &ast::Variant,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<P<ast::Expr>, Error> {
+    let variant_ident = variant.node.name;
+
+    match variant.node.data {
+        // Internally tagged unit variants carry no content of their own,
+        // so there is nothing left to read out of `__deserializer`.
+        ast::VariantData::Unit(_) => {
+            Ok(quote_expr!(cx, Ok($type_ident::$variant_ident)))
+        }
+        ast::VariantData::Tuple(ref args, _) if args.len() == 1 => {
+            Ok(quote_expr!(cx, {
+                let value = try!(::serde::de::Deserialize::deserialize(&mut __deserializer));
+                Ok($type_ident::$variant_ident(value))
+            }))
+        }
+        ast::VariantData::Tuple(..) => {
+            cx.span_err(
+                variant.span,
+                "tuple variants are not supported in internally tagged enums");
+            Err(Error)
+        }
+        ast::VariantData::Struct(ref fields, _) => {
+            let (items, fields_stmt, visitor_expr) = try!(deserialize_struct_variant_from_content(
+                cx,
+                builder,
+                type_ident,
+                variant_ident,
+                generics,
+                ty,
+                fields,
+                container_attrs,
+            ));
+
+            Ok(quote_expr!(cx, {
+                $items
+
+                $fields_stmt
+
+                ::serde::de::Deserializer::deserialize(&mut __deserializer, $visitor_expr)
+            }))
+        }
+    }
+}
+
+fn deserialize_item_enum_adjacently_tagged(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    impl_generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    enum_def: &EnumDef,
+    container_attrs: &attr::ContainerAttrs,
+    tag_name: InternedString,
+    content_name: InternedString,
+) -> Result<P<ast::Expr>, Error> {
+    let content_items = content_items(cx);
+    let tag_name_expr = builder.expr().str(&tag_name[..]);
+    let content_name_expr = builder.expr().str(&content_name[..]);
+
+    let variant_attrs: Vec<_> = try!(
+        enum_def.variants.iter()
+            .map(|variant| attr::VariantAttrs::from_variant(cx, variant))
+            .collect()
+    );
+
+    let other_idx = try!(unique_other_variant(cx, enum_def, &variant_attrs));
+
+    let mut variant_arms = vec![];
+    for (i, variant) in enum_def.variants.iter().enumerate() {
+        if Some(i) == other_idx {
+            continue;
+        }
+
+        let guard_expr = variant_tag_guard_expr(cx, builder, &variant_attrs[i]);
+
+        let expr = try!(deserialize_adjacently_tagged_variant(
+            cx,
+            builder,
+            type_ident,
+            impl_generics,
+            ty.clone(),
+            variant,
+            container_attrs,
+        ));
+
+        variant_arms.push(quote_arm!(cx, ref __tag if $guard_expr => { $expr }));
+    }
+
+    let unknown_variant_arm = match other_idx {
+        Some(idx) => {
+            let variant_ident = enum_def.variants[idx].node.name;
+            quote_arm!(cx, _ => { Ok($type_ident::$variant_ident) })
+        }
+        None => quote_arm!(cx, ref __tag => Err(::serde::de::Error::unknown_variant(__tag))),
+    };
+
+    Ok(quote_expr!(cx, {
+        $content_items
+
+        let __content = try!(<Content as ::serde::de::Deserialize>::deserialize(deserializer));
+
+        let (__tag, __content) = match __content {
+            Content::Map(__map) => {
+                let mut __tag = None;
+                let mut __content = None;
+
+                for (__key, __value) in __map {
+                    match __key {
+                        Content::String(ref __s) if &__s[..] == $tag_name_expr => {
+                            __tag = Some(__value);
+                        }
+                        Content::String(ref __s) if &__s[..] == $content_name_expr => {
+                            __content = Some(__value);
+                        }
+                        _ => {}
+                    }
+                }
+
+                let __tag = match __tag {
+                    Some(Content::String(__tag)) => __tag,
+                    Some(_) => {
+                        return Err(::serde::de::Error::custom("expected string tag"));
+                    }
+                    None => {
+                        return Err(::serde::de::Error::missing_field($tag_name_expr));
+                    }
+                };
+
+                (__tag, __content.unwrap_or(Content::Unit))
+            }
+            _ => {
+                return Err(::serde::de::Error::custom("expected adjacently tagged enum"));
+            }
+        };
+
+        let mut __deserializer = ContentDeserializer::<__D::Error>::new(__content);
+
+        match __tag {
+            $variant_arms
+            $unknown_variant_arm
+        }
+    }))
+}
+
+fn deserialize_adjacently_tagged_variant(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    variant: &ast::Variant,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<P<ast::Expr>, Error> {
+    let variant_ident = variant.node.name;
+
+    match variant.node.data {
+        ast::VariantData::Unit(_) => {
+            Ok(quote_expr!(cx, Ok($type_ident::$variant_ident)))
+        }
+        ast::VariantData::Tuple(ref args, _) if args.len() == 1 => {
+            Ok(quote_expr!(cx, {
+                let value = try!(::serde::de::Deserialize::deserialize(&mut __deserializer));
+                Ok($type_ident::$variant_ident(value))
+            }))
+        }
+        ast::VariantData::Tuple(ref fields, _) => {
+            let (visitor_item, visitor_ty, visitor_expr, visitor_generics) = try!(deserialize_visitor(
+                builder,
+                generics,
+                vec![],
+                vec![],
+            ));
+
+            let visit_seq_expr = deserialize_seq(
+                cx,
+                builder,
+                builder.path().id(type_ident).id(variant_ident).build(),
+                fields.len(),
+            );
+
+            let where_clause = &generics.where_clause;
+
+            Ok(quote_expr!(cx, {
+                $visitor_item
+
+                impl $visitor_generics ::serde::de::Visitor for $visitor_ty $where_clause {
+                    type Value = $ty;
+
+                    #[inline]
+                    fn visit_seq<__V>(&mut self, mut visitor: __V) -> ::std::result::Result<$ty, __V::Error>
+                        where __V: ::serde::de::SeqVisitor,
+                    {
+                        $visit_seq_expr
+                    }
+                }
+
+                ::serde::de::Deserializer::deserialize(&mut __deserializer, $visitor_expr)
+            }))
+        }
+        ast::VariantData::Struct(ref fields, _) => {
+            let (items, fields_stmt, visitor_expr) = try!(deserialize_struct_variant_from_content(
+                cx,
+                builder,
+                type_ident,
+                variant_ident,
+                generics,
+                ty,
+                fields,
+                container_attrs,
+            ));
+
+            Ok(quote_expr!(cx, {
+                $items
+
+                $fields_stmt
+
+                ::serde::de::Deserializer::deserialize(&mut __deserializer, $visitor_expr)
+            }))
+        }
+    }
+}
+
+fn deserialize_item_enum_untagged(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    impl_generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    enum_def: &EnumDef,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<P<ast::Expr>, Error> {
+    let content_items = content_items(cx);
+
+    let mut variant_attempts = vec![];
+    let mut tried_names = vec![];
+    for variant in enum_def.variants.iter() {
+        let variant_attrs = try!(attr::VariantAttrs::from_variant(cx, variant));
+
+        // Untagged enums pick a variant by trying each one's own
+        // `Deserialize` impl against the buffered content, never by
+        // comparing a tag string to a name -- so there's no string match
+        // for `#[serde(alias = "...")]` to widen, and no "no variant
+        // matched" case for `#[serde(other)]` to catch. Reject both
+        // outright rather than silently ignoring them.
+        if !variant_attrs.aliases().is_empty() {
+            cx.span_err(
+                variant.span,
+                "`#[serde(alias = \"...\")]` may not be used on a variant of an untagged enum");
+            return Err(Error);
+        }
+        if variant_attrs.other() {
+            cx.span_err(
+                variant.span,
+                "`#[serde(other)]` may not be used on a variant of an untagged enum");
+            return Err(Error);
+        }
+
+        tried_names.push(variant_attrs.name().deserialize_name());
+
+        let attempt = try!(deserialize_untagged_variant(
+            cx,
+            builder,
+            type_ident,
+            impl_generics,
+            ty.clone(),
+            variant,
+            container_attrs,
+        ));
+
+        variant_attempts.push(attempt);
+    }
+
+    let tried_names_msg = format!(
+        "data did not match any variant of untagged enum; tried {}",
+        tried_names.iter().map(|n| format!("`{}`", n)).collect::<Vec<_>>().join(", "),
+    );
+    let tried_names_expr = builder.expr().str(&tried_names_msg[..]);
+
+    Ok(quote_expr!(cx, {
+        $content_items
+
+        let __content = try!(<Content as ::serde::de::Deserialize>::deserialize(deserializer));
+
+        $variant_attempts
+
+        Err(::serde::de::Error::custom($tried_names_expr))
+    }))
+}
+
+fn deserialize_untagged_variant(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    variant: &ast::Variant,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<P<ast::Stmt>, Error> {
+    let variant_ident = variant.node.name;
+
+    match variant.node.data {
+        ast::VariantData::Unit(_) => {
+            Ok(quote_stmt!(cx, {
+                struct __UnitVisitor;
+
+                impl ::serde::de::Visitor for __UnitVisitor {
+                    type Value = ();
+
+                    #[inline]
+                    fn visit_unit<__E>(&mut self) -> ::std::result::Result<(), __E>
+                        where __E: ::serde::de::Error,
+                    {
+                        Ok(())
+                    }
+                }
+
+                let mut __de = ContentDeserializer::<__D::Error>::new(__content.clone());
+                if let Ok(()) = ::serde::de::Deserializer::deserialize(&mut __de, __UnitVisitor) {
+                    return Ok($type_ident::$variant_ident);
+                }
+            }).unwrap())
+        }
+        ast::VariantData::Tuple(ref args, _) if args.len() == 1 => {
+            Ok(quote_stmt!(cx, {
+                let mut __de = ContentDeserializer::<__D::Error>::new(__content.clone());
+                if let Ok(value) = ::serde::de::Deserialize::deserialize(&mut __de) {
+                    return Ok($type_ident::$variant_ident(value));
+                }
+            }).unwrap())
+        }
+        ast::VariantData::Tuple(ref fields, _) => {
+            let visit_seq_expr = deserialize_seq(
+                cx,
+                builder,
+                builder.path().id(type_ident).id(variant_ident).build(),
+                fields.len(),
+            );
+
+            Ok(quote_stmt!(cx, {
+                struct __TupleVisitor;
+
+                impl ::serde::de::Visitor for __TupleVisitor {
+                    type Value = $ty;
+
+                    #[inline]
+                    fn visit_seq<__V>(&mut self, mut visitor: __V) -> ::std::result::Result<$ty, __V::Error>
+                        where __V: ::serde::de::SeqVisitor,
+                    {
+                        $visit_seq_expr
+                    }
+                }
+
+                let mut __de = ContentDeserializer::<__D::Error>::new(__content.clone());
+                if let Ok(value) = ::serde::de::Deserializer::deserialize(&mut __de, __TupleVisitor) {
+                    return Ok(value);
+                }
+            }).unwrap())
+        }
+        ast::VariantData::Struct(ref fields, _) => {
+            let (items, fields_stmt, visitor_expr) = try!(deserialize_struct_variant_from_content(
+                cx,
+                builder,
+                type_ident,
+                variant_ident,
+                generics,
+                ty,
+                fields,
+                container_attrs,
+            ));
+
+            Ok(quote_stmt!(cx, {
+                $items
+
+                $fields_stmt
+
+                let mut __de = ContentDeserializer::<__D::Error>::new(__content.clone());
+                if let Ok(value) = ::serde::de::Deserializer::deserialize(&mut __de, $visitor_expr) {
+                    return Ok(value);
+                }
+            }).unwrap())
+        }
+    }
+}
+
+fn deserialize_variant(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: Ident,
+    generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    variant: &ast::Variant,
+    container_attrs: &attr::ContainerAttrs,
+) -> Result<P<ast::Expr>, Error> {
+    let variant_ident = variant.node.name;
+
+    match variant.node.data {
+        ast::VariantData::Unit(_) => {
+            Ok(quote_expr!(cx, {
+                try!(visitor.visit_unit());
+                Ok($type_ident::$variant_ident)
+            }))
+        }
+        ast::VariantData::Tuple(ref args, _) if args.len() == 1 => {
+            Ok(quote_expr!(cx, {
+                let val = try!(visitor.visit_newtype());
+                Ok($type_ident::$variant_ident(val))
+            }))
+        }
+        ast::VariantData::Tuple(ref fields, _) => {
+            deserialize_tuple_variant(
+                cx,
+                builder,
+                type_ident,
+                variant_ident,
+                generics,
+                ty,
+                fields.len(),
+            )
+        }
+        ast::VariantData::Struct(ref fields, _) => {
+            deserialize_struct_variant(
+                cx,
+                builder,
+                type_ident,
+                variant_ident,
+                generics,
+                ty,
+                fields,
+                container_attrs,
+            )
+        }
+    }
+}
+
+fn deserialize_tuple_variant(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: ast::Ident,
+    variant_ident: ast::Ident,
+    generics: &ast::Generics,
+    ty: P<ast::Ty>,
+    fields: usize,
+) -> Result<P<ast::Expr>, Error> {
+    let where_clause = &generics.where_clause;
+
+    let (visitor_item, visitor_ty, visitor_expr, visitor_generics) = try!(deserialize_visitor(
+        builder,
+        generics,
+        vec![deserializer_ty_param(builder)],
+        vec![deserializer_ty_arg(builder)],
+    ));
+
+    let visit_seq_expr = deserialize_seq(
+        cx,
+        builder,
+        builder.path().id(type_ident).id(variant_ident).build(),
+        fields,
+    );
+
+    Ok(quote_expr!(cx, {
+        $visitor_item
+
+        impl $visitor_generics ::serde::de::Visitor for $visitor_ty $where_clause {
+            type Value = $ty;
+
+            fn visit_seq<__V>(&mut self, mut visitor: __V) -> ::std::result::Result<$ty, __V::Error>
+                where __V: ::serde::de::SeqVisitor,
+            {
+                $visit_seq_expr
+            }
+        }
+
+        visitor.visit_tuple($fields, $visitor_expr)
+    }))
+}
+
+fn deserialize_struct_variant(
+    cx: &ExtCtxt,
+    builder: &aster::AstBuilder,
+    type_ident: ast::Ident,
     variant_ident: ast::Ident,
     generics: &ast::Generics,
     ty: P<ast::Ty>,
@@ -754,7 +1958,11 @@ fn deserialize_struct_variant(
         cx,
         builder,
         type_path.clone(),
+        &ty,
+        generics,
         fields,
+        container_attrs,
+        true,
     ));
 
     let (field_visitor, fields_stmt, field_expr) = try!(deserialize_struct_visitor(
@@ -807,10 +2015,14 @@ fn deserialize_struct_variant(
 fn deserialize_field_visitor(
     cx: &ExtCtxt,
     builder: &aster::AstBuilder,
-    field_names: Vec<InternedString>,
+    fields: Vec<(InternedString, Vec<InternedString>)>,
     container_attrs: &attr::ContainerAttrs,
     is_variant: bool,
+    other_idx: Option<usize>,
 ) -> Vec<P<ast::Item>> {
+    let field_names: Vec<InternedString> = fields.iter().map(|&(ref name, _)| name.clone()).collect();
+    let aliases: Vec<Vec<InternedString>> = fields.iter().map(|&(_, ref aliases)| aliases.clone()).collect();
+
     // Create the field names for the fields.
     let field_idents: Vec<_> = (0 .. field_names.len())
         .map(|i| builder.id(format!("__field{}", i)))
@@ -847,12 +2059,20 @@ fn deserialize_field_visitor(
         (builder.expr().str("expected a field"), builder.id("unknown_field"))
     };
 
-    let fallthrough_index_arm_expr = if !is_variant && !container_attrs.deny_unknown_fields() {
-        quote_expr!(cx, Ok(__Field::__ignore))
-    } else {
-        quote_expr!(cx, {
-            Err(::serde::de::Error::invalid_value($index_error_msg))
-        })
+    // `#[serde(other)]` on a unit variant catches any tag that doesn't
+    // match one of the known variants, instead of failing outright.
+    let other_field_ident = other_idx.map(|i| field_idents[i].clone());
+
+    let fallthrough_index_arm_expr = match other_field_ident {
+        Some(ref field_ident) => quote_expr!(cx, Ok(__Field::$field_ident)),
+        None if !is_variant && !container_attrs.deny_unknown_fields() => {
+            quote_expr!(cx, Ok(__Field::__ignore))
+        }
+        None => {
+            quote_expr!(cx, {
+                Err(::serde::de::Error::invalid_value($index_error_msg))
+            })
+        }
     };
 
     let index_body = quote_expr!(cx,
@@ -862,22 +2082,25 @@ fn deserialize_field_visitor(
         }
     );
 
-    // Convert the field names into byte strings.
-    let str_field_names: Vec<_> = field_names.iter()
-        .map(|name| builder.expr().lit().str(&name))
-        .collect();
-
-    // Match arms to extract a field from a string
-    let str_field_arms: Vec<_> = field_idents.iter().zip(str_field_names.iter())
-        .map(|(field_ident, field_name)| {
-            quote_arm!(cx, $field_name => { Ok(__Field::$field_ident) })
+    // Match arms to extract a field from a string, one per accepted name
+    // (the primary name plus any `#[serde(alias = "...")]` aliases).
+    let str_field_arms: Vec<_> = field_idents.iter().zip(field_names.iter()).zip(aliases.iter())
+        .flat_map(|((field_ident, name), aliases)| {
+            Some(name).into_iter().chain(aliases.iter())
+                .map(|name| builder.expr().lit().str(&name[..]))
+                .map(move |name_expr| {
+                    quote_arm!(cx, $name_expr => { Ok(__Field::$field_ident) })
+                })
+                .collect::<Vec<_>>()
         })
         .collect();
 
-    let fallthrough_str_arm_expr = if !is_variant && !container_attrs.deny_unknown_fields() {
-        quote_expr!(cx, Ok(__Field::__ignore))
-    } else {
-        quote_expr!(cx, Err(::serde::de::Error::$unknown_ident(value)))
+    let fallthrough_str_arm_expr = match other_field_ident {
+        Some(ref field_ident) => quote_expr!(cx, Ok(__Field::$field_ident)),
+        None if !is_variant && !container_attrs.deny_unknown_fields() => {
+            quote_expr!(cx, Ok(__Field::__ignore))
+        }
+        None => quote_expr!(cx, Err(::serde::de::Error::$unknown_ident(value))),
     };
 
     let str_body = quote_expr!(cx,
@@ -887,28 +2110,33 @@ fn deserialize_field_visitor(
         }
     );
 
-    // Convert the field names into byte strings.
-    let bytes_field_names: Vec<_> = field_names.iter()
-        .map(|name| {
-            let name: &str = name;
-            builder.expr().lit().byte_str(name)
-        })
-        .collect();
-
-    // Match arms to extract a field from a string
-    let bytes_field_arms: Vec<_> = field_idents.iter().zip(bytes_field_names.iter())
-        .map(|(field_ident, field_name)| {
-            quote_arm!(cx, $field_name => { Ok(__Field::$field_ident) })
+    // Match arms to extract a field from a byte string, one per accepted
+    // name.
+    let bytes_field_arms: Vec<_> = field_idents.iter().zip(field_names.iter()).zip(aliases.iter())
+        .flat_map(|((field_ident, name), aliases)| {
+            Some(name).into_iter().chain(aliases.iter())
+                .map(|name| {
+                    let name: &str = name;
+                    builder.expr().lit().byte_str(name)
+                })
+                .map(move |name_expr| {
+                    quote_arm!(cx, $name_expr => { Ok(__Field::$field_ident) })
+                })
+                .collect::<Vec<_>>()
         })
         .collect();
 
-    let fallthrough_bytes_arm_expr = if !is_variant && !container_attrs.deny_unknown_fields() {
-        quote_expr!(cx, Ok(__Field::__ignore))
-    } else {
-        quote_expr!(cx, {
-            let value = ::std::string::String::from_utf8_lossy(value);
-            Err(::serde::de::Error::$unknown_ident(&value))
-        })
+    let fallthrough_bytes_arm_expr = match other_field_ident {
+        Some(ref field_ident) => quote_expr!(cx, Ok(__Field::$field_ident)),
+        None if !is_variant && !container_attrs.deny_unknown_fields() => {
+            quote_expr!(cx, Ok(__Field::__ignore))
+        }
+        None => {
+            quote_expr!(cx, {
+                let value = ::std::string::String::from_utf8_lossy(value);
+                Err(::serde::de::Error::$unknown_ident(&value))
+            })
+        }
     };
 
     let bytes_body = quote_expr!(cx,
@@ -979,9 +2207,10 @@ fn deserialize_struct_visitor(
                                              container_ty,
                                              generics,
                                              field,
-                                             is_enum)
+                                             is_enum,
+                                             container_attrs.default())
             );
-            Ok(field_attrs.name().deserialize_name())
+            Ok((field_attrs.name().deserialize_name(), field_attrs.aliases().to_vec()))
         })
         .collect();
 
@@ -991,6 +2220,7 @@ fn deserialize_struct_visitor(
         try!(field_exprs),
         container_attrs,
         false,
+        None,
     );
 
     let visit_map_expr = try!(deserialize_map(
@@ -1042,7 +2272,14 @@ fn deserialize_map(
 
     let field_attrs: Vec<_> = try!(
         fields.iter()
-            .map(|field| attr::FieldAttrs::from_field(cx, container_ty, generics, field, is_enum))
+            .map(|field| attr::FieldAttrs::from_field(
+                cx,
+                container_ty,
+                generics,
+                field,
+                is_enum,
+                container_attrs.default(),
+            ))
             .collect()
     );
 
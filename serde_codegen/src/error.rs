@@ -0,0 +1,9 @@
+/// A marker type returned by the attribute-parsing and codegen helpers in
+/// this crate when something has gone wrong.
+///
+/// The error itself carries no information because by the time one is
+/// constructed, a human-readable diagnostic has already been emitted (via
+/// `ExtCtxt::span_err`, or by emitting a parser error). Callers simply propagate it with `try!` until it
+/// reaches `expand_derive_serialize`/`expand_derive_deserialize`, where it is
+/// used to bail out without generating any code.
+pub struct Error;